@@ -1127,4 +1127,25 @@ mod test {
         assert_eq!(slot_to_key(0), "0000000000000000");
         assert_eq!(slot_to_key(!0), "ffffffffffffffff");
     }
+
+    #[test]
+    fn test_key_to_slot() {
+        let slots: Vec<Slot> = (0..1_000)
+            .chain([65_535, 65_536, Slot::from(u32::MAX), !0 - 1, !0])
+            .collect();
+
+        for &slot in &slots {
+            assert_eq!(key_to_slot(&slot_to_key(slot)), Some(slot));
+            assert_eq!(key_to_slot(&slot_to_blocks_key(slot)), Some(slot));
+            assert_eq!(key_to_slot(&slot_to_tx_by_addr_key(slot)), Some(!slot));
+        }
+
+        // blocks keys sort with their slots, tx-by-addr keys sort in reverse
+        for pair in slots.windows(2) {
+            assert!(slot_to_blocks_key(pair[0]) < slot_to_blocks_key(pair[1]));
+            assert!(slot_to_tx_by_addr_key(pair[0]) > slot_to_tx_by_addr_key(pair[1]));
+        }
+
+        assert_eq!(key_to_slot("not a slot"), None);
+    }
 }
@@ -2,7 +2,22 @@
 use {crate::errors::TranscriptError, thiserror::Error};
 
 #[derive(Error, Clone, Debug, Eq, PartialEq)]
-pub enum RangeProofGenerationError {}
+pub enum RangeProofGenerationError {
+    #[error("attempted to create range proof with a non-power-of-two bit size")]
+    InvalidBitSize,
+    #[error("attempted to create range proof with a bit size that is too big")]
+    BitSizeTooLarge,
+    #[error("value is not within the range to be proven")]
+    ValueOutOfRange,
+    #[error(
+        "inconsistent input lengths: {values} values, {bit_sizes} bit sizes, {blindings} blindings"
+    )]
+    InconsistentInputLengths {
+        values: usize,
+        bit_sizes: usize,
+        blindings: usize,
+    },
+}
 
 #[derive(Error, Clone, Debug, Eq, PartialEq)]
 pub enum RangeProofVerificationError {
@@ -12,12 +27,36 @@ pub enum RangeProofVerificationError {
     Deserialization,
     #[error("multiscalar multiplication failed")]
     MultiscalarMul,
-    #[error("transcript failed to produce a challenge")]
-    Transcript(#[from] TranscriptError),
-    #[error(
-        "attempted to verify range proof with a non-power-of-two bit size or bit size is too big"
-    )]
+    #[error("transcript failed to produce a challenge at `{label}`")]
+    Transcript {
+        label: String,
+        source: TranscriptError,
+    },
+    #[error("attempted to verify range proof with a non-power-of-two bit size")]
     InvalidBitSize,
+    #[error("attempted to verify range proof with a bit size that is too big")]
+    BitSizeTooLarge,
     #[error("insufficient generators for the proof")]
     InvalidGeneratorsLength,
+    /// An aggregated proof over zero commitments certifies nothing, so an empty batch is treated
+    /// as a caller error rather than vacuously accepted.
+    #[error("attempted to verify range proof on an empty batch of commitments")]
+    EmptyBatch,
+    #[error("attempted to verify range proof on an empty range where min is greater than max")]
+    InvalidRange,
+    #[error("inconsistent input lengths: {commitments} commitments, {bit_sizes} bit sizes")]
+    InconsistentInputLengths {
+        commitments: usize,
+        bit_sizes: usize,
+    },
+}
+
+impl RangeProofVerificationError {
+    /// Annotate a transcript failure with the label of the proof component that failed.
+    pub(crate) fn transcript(label: impl Into<String>, source: TranscriptError) -> Self {
+        Self::Transcript {
+            label: label.into(),
+            source,
+        }
+    }
 }
@@ -219,9 +219,17 @@ impl InnerProductProof {
         // 1. Recompute x_k,...,x_1 based on the proof transcript
 
         let mut challenges = Vec::with_capacity(lg_n);
-        for (L, R) in self.L_vec.iter().zip(self.R_vec.iter()) {
-            transcript.validate_and_append_point(b"L", L)?;
-            transcript.validate_and_append_point(b"R", R)?;
+        for (round, (L, R)) in self.L_vec.iter().zip(self.R_vec.iter()).enumerate() {
+            transcript
+                .validate_and_append_point(b"L", L)
+                .map_err(|source| {
+                    RangeProofVerificationError::transcript(format!("L_{round}"), source)
+                })?;
+            transcript
+                .validate_and_append_point(b"R", R)
+                .map_err(|source| {
+                    RangeProofVerificationError::transcript(format!("R_{round}"), source)
+                })?;
             challenges.push(transcript.challenge_scalar(b"u"));
         }
 
@@ -41,6 +41,72 @@ pub mod generators;
 pub mod inner_product;
 pub mod util;
 
+/// The sums of bit-lengths for which an aggregated range proof can be created and verified.
+const SUPPORTED_BIT_SIZES: [usize; 9] = [1, 2, 4, 8, 16, 32, 64, 128, 256];
+
+/// Returns the sums of bit-lengths that are supported by the range proof.
+pub fn supported_bit_sizes() -> &'static [usize] {
+    &SUPPORTED_BIT_SIZES
+}
+
+/// Returns whether an aggregated range proof with total bit-length `n` is supported.
+pub fn is_bit_size_supported(n: usize) -> bool {
+    supported_bit_sizes().contains(&n)
+}
+
+/// The largest supported sum of bit-lengths.
+const MAX_BIT_SIZE: usize = SUPPORTED_BIT_SIZES[SUPPORTED_BIT_SIZES.len() - 1];
+
+/// The reason a sum of bit-lengths is not supported by the range proof.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum UnsupportedBitSize {
+    Invalid,
+    TooLarge,
+}
+
+impl From<UnsupportedBitSize> for RangeProofGenerationError {
+    fn from(err: UnsupportedBitSize) -> Self {
+        match err {
+            UnsupportedBitSize::Invalid => Self::InvalidBitSize,
+            UnsupportedBitSize::TooLarge => Self::BitSizeTooLarge,
+        }
+    }
+}
+
+impl From<UnsupportedBitSize> for RangeProofVerificationError {
+    fn from(err: UnsupportedBitSize) -> Self {
+        match err {
+            UnsupportedBitSize::Invalid => Self::InvalidBitSize,
+            UnsupportedBitSize::TooLarge => Self::BitSizeTooLarge,
+        }
+    }
+}
+
+/// Check that an aggregated range proof with total bit-length `nm` can be created and verified.
+fn check_bit_size(nm: usize) -> Result<(), UnsupportedBitSize> {
+    if is_bit_size_supported(nm) {
+        Ok(())
+    } else if nm > MAX_BIT_SIZE {
+        Err(UnsupportedBitSize::TooLarge)
+    } else {
+        Err(UnsupportedBitSize::Invalid)
+    }
+}
+
+/// Check that every commitment to be verified has a matching bit-length.
+fn check_verification_input_lengths(
+    comms: &[&PedersenCommitment],
+    bit_lengths: &[usize],
+) -> Result<(), RangeProofVerificationError> {
+    if comms.len() != bit_lengths.len() {
+        return Err(RangeProofVerificationError::InconsistentInputLengths {
+            commitments: comms.len(),
+            bit_sizes: bit_lengths.len(),
+        });
+    }
+    Ok(())
+}
+
 #[allow(non_snake_case)]
 #[derive(Clone)]
 pub struct RangeProof {
@@ -64,7 +130,8 @@ impl RangeProof {
     /// - bit-lengths of the committed amounts
     /// - Pedersen openings for each commitments
     ///
-    /// The sum of the bit-lengths of the commitments amounts must be a power-of-two
+    /// The sum of the bit-lengths of the commitments amounts must be one of the
+    /// [`supported_bit_sizes`].
     #[allow(clippy::many_single_char_names)]
     #[cfg(not(target_os = "solana"))]
     pub fn new(
@@ -75,12 +142,17 @@ impl RangeProof {
     ) -> Result<Self, RangeProofGenerationError> {
         // amounts, bit-lengths, openings must be same length vectors
         let m = amounts.len();
-        assert_eq!(bit_lengths.len(), m);
-        assert_eq!(openings.len(), m);
+        if bit_lengths.len() != m || openings.len() != m {
+            return Err(RangeProofGenerationError::InconsistentInputLengths {
+                values: m,
+                bit_sizes: bit_lengths.len(),
+                blindings: openings.len(),
+            });
+        }
 
         // total vector dimension to compute the ultimate inner product proof for
         let nm: usize = bit_lengths.iter().sum();
-        assert!(nm.is_power_of_two());
+        check_bit_size(nm)?;
 
         let bp_gens = BulletproofGens::new(nm);
 
@@ -229,27 +301,104 @@ impl RangeProof {
         })
     }
 
-    #[allow(clippy::many_single_char_names)]
+    /// Create a range proof that a committed amount lies in the range `[min, max]`.
+    ///
+    /// The proof is created with respect to the Pedersen commitment `Pedersen::with(amount,
+    /// opening)`. It is an aggregated proof that both `amount - min` and `max - amount` are in
+    /// `[0, 2^n)`, where `n` is the smallest power-of-two such that `2^n > max - min`. Proving only
+    /// `amount - min` would bound the amount by `min + 2^n - 1` rather than by `max`.
+    ///
+    /// The commitment and the bounds are appended to the transcript, so the proof only verifies
+    /// against the same commitment and range.
+    ///
+    /// Returns `ValueOutOfRange` if `amount` is not in `[min, max]`, which includes every amount
+    /// when `min > max`.
+    #[cfg(not(target_os = "solana"))]
+    pub fn new_with_bounds(
+        amount: u64,
+        min: u64,
+        max: u64,
+        opening: &PedersenOpening,
+        transcript: &mut Transcript,
+    ) -> Result<Self, RangeProofGenerationError> {
+        if amount < min || amount > max {
+            return Err(RangeProofGenerationError::ValueOutOfRange);
+        }
+
+        // the width of a `u64` range always fits in 64 bits, so the aggregated bit-length is at
+        // most 128 and always supported
+        let bit_length = bounded_bit_length(min, max);
+        debug_assert!(is_bit_size_supported(2 * bit_length));
+
+        let comm = Pedersen::with(amount, opening);
+        transcript.append_point(b"commitment", &comm.get_point().compress());
+        transcript.append_u64(b"min", min);
+        transcript.append_u64(b"max", max);
+
+        let negated_opening = PedersenOpening::new(-opening.get_scalar());
+        RangeProof::new(
+            vec![amount - min, max - amount],
+            vec![bit_length, bit_length],
+            vec![opening, &negated_opening],
+            transcript,
+        )
+    }
+
     pub fn verify(
         &self,
         comms: Vec<&PedersenCommitment>,
         bit_lengths: Vec<usize>,
         transcript: &mut Transcript,
+    ) -> Result<(), RangeProofVerificationError> {
+        check_verification_input_lengths(&comms, &bit_lengths)?;
+        if comms.is_empty() {
+            return Err(RangeProofVerificationError::EmptyBatch);
+        }
+
+        let nm: usize = bit_lengths.iter().sum();
+        check_bit_size(nm)?;
+
+        let bp_gens = BulletproofGens::new(nm);
+
+        self.verify_with_generators(&bp_gens, comms, bit_lengths, transcript)
+    }
+
+    /// Verify an aggregated range proof with respect to a precomputed set of generators.
+    ///
+    /// Generating the Bulletproofs generators is a significant part of the verification cost. A
+    /// verifier that checks many proofs of the same total bit-length can construct the generators
+    /// once and reuse them across calls. The capacity of `bp_gens` must be at least the sum of the
+    /// bit-lengths; only the first generators of a larger set are used, so a single set sized for
+    /// the largest supported bit-length can be shared across proofs of any smaller size.
+    #[allow(clippy::many_single_char_names)]
+    pub fn verify_with_generators(
+        &self,
+        bp_gens: &BulletproofGens,
+        comms: Vec<&PedersenCommitment>,
+        bit_lengths: Vec<usize>,
+        transcript: &mut Transcript,
     ) -> Result<(), RangeProofVerificationError> {
         // commitments and bit-lengths must be same length vectors
-        assert_eq!(comms.len(), bit_lengths.len());
+        check_verification_input_lengths(&comms, &bit_lengths)?;
+        if comms.is_empty() {
+            return Err(RangeProofVerificationError::EmptyBatch);
+        }
 
         let m = bit_lengths.len();
         let nm: usize = bit_lengths.iter().sum();
-        let bp_gens = BulletproofGens::new(nm);
 
-        if !nm.is_power_of_two() {
-            return Err(RangeProofVerificationError::InvalidBitSize);
+        check_bit_size(nm)?;
+        if bp_gens.gens_capacity < nm {
+            return Err(RangeProofVerificationError::InvalidGeneratorsLength);
         }
 
         // append proof data to transcript and derive appropriate challenge scalars
-        transcript.validate_and_append_point(b"A", &self.A)?;
-        transcript.validate_and_append_point(b"S", &self.S)?;
+        transcript
+            .validate_and_append_point(b"A", &self.A)
+            .map_err(|source| RangeProofVerificationError::transcript("A", source))?;
+        transcript
+            .validate_and_append_point(b"S", &self.S)
+            .map_err(|source| RangeProofVerificationError::transcript("S", source))?;
 
         let y = transcript.challenge_scalar(b"y");
         let z = transcript.challenge_scalar(b"z");
@@ -257,8 +406,12 @@ impl RangeProof {
         let zz = z * z;
         let minus_z = -z;
 
-        transcript.validate_and_append_point(b"T_1", &self.T_1)?;
-        transcript.validate_and_append_point(b"T_2", &self.T_2)?;
+        transcript
+            .validate_and_append_point(b"T_1", &self.T_1)
+            .map_err(|source| RangeProofVerificationError::transcript("T_1", source))?;
+        transcript
+            .validate_and_append_point(b"T_2", &self.T_2)
+            .map_err(|source| RangeProofVerificationError::transcript("T_2", source))?;
 
         let x = transcript.challenge_scalar(b"x");
 
@@ -330,6 +483,36 @@ impl RangeProof {
         }
     }
 
+    /// Verify a range proof created by [`RangeProof::new_with_bounds`] that the amount committed in
+    /// `comm` lies in the range `[min, max]`.
+    pub fn verify_with_bounds(
+        &self,
+        comm: &PedersenCommitment,
+        min: u64,
+        max: u64,
+        transcript: &mut Transcript,
+    ) -> Result<(), RangeProofVerificationError> {
+        if min > max {
+            return Err(RangeProofVerificationError::InvalidRange);
+        }
+
+        let bit_length = bounded_bit_length(min, max);
+
+        transcript.append_point(b"commitment", &comm.get_point().compress());
+        transcript.append_u64(b"min", min);
+        transcript.append_u64(b"max", max);
+
+        // commitments to `amount - min` and `max - amount`
+        let lower_comm = comm - Pedersen::encode(min);
+        let upper_comm = Pedersen::encode(max) - comm;
+
+        self.verify(
+            vec![&lower_comm, &upper_comm],
+            vec![bit_length, bit_length],
+            transcript,
+        )
+    }
+
     // Following the dalek rangeproof library signature for now. The exact method signature can be
     // changed.
     pub fn to_bytes(&self) -> Vec<u8> {
@@ -400,9 +583,16 @@ fn delta(bit_lengths: &[usize], y: &Scalar, z: &Scalar) -> Scalar {
     agg_delta
 }
 
+/// Compute the smallest power-of-two bit-length `n` such that `2^n > max - min`.
+fn bounded_bit_length(min: u64, max: u64) -> usize {
+    let width = max - min;
+    let bits = (u64::BITS - width.leading_zeros()).max(1);
+    bits.next_power_of_two() as usize
+}
+
 #[cfg(test)]
 mod tests {
-    use super::*;
+    use {super::*, crate::errors::TranscriptError, curve25519_dalek::traits::Identity};
 
     #[test]
     fn test_single_rangeproof() {
@@ -445,5 +635,386 @@ mod tests {
             .is_ok());
     }
 
+    #[test]
+    fn test_rangeproof_with_bounds() {
+        let (min, max) = (1_000_u64, 1_100_u64);
+
+        for amount in [min, 1_055, max] {
+            let (comm, open) = Pedersen::new(amount);
+
+            let mut transcript_create = Transcript::new(b"Test");
+            let mut transcript_verify = Transcript::new(b"Test");
+
+            let proof =
+                RangeProof::new_with_bounds(amount, min, max, &open, &mut transcript_create)
+                    .unwrap();
+
+            assert!(proof
+                .verify_with_bounds(&comm, min, max, &mut transcript_verify)
+                .is_ok());
+        }
+
+        // the proof does not verify against a tighter range
+        let (comm, open) = Pedersen::new(1_055_u64);
+
+        let mut transcript_create = Transcript::new(b"Test");
+        let mut transcript_verify = Transcript::new(b"Test");
+
+        let proof =
+            RangeProof::new_with_bounds(1_055, min, max, &open, &mut transcript_create).unwrap();
+
+        assert!(proof
+            .verify_with_bounds(&comm, 1_060, max, &mut transcript_verify)
+            .is_err());
+
+        // the full `u64` range is supported
+        let (comm, open) = Pedersen::new(u64::MAX);
+
+        let mut transcript_create = Transcript::new(b"Test");
+        let mut transcript_verify = Transcript::new(b"Test");
+
+        let proof =
+            RangeProof::new_with_bounds(u64::MAX, 0, u64::MAX, &open, &mut transcript_create)
+                .unwrap();
+
+        assert!(proof
+            .verify_with_bounds(&comm, 0, u64::MAX, &mut transcript_verify)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_rangeproof_with_bounds_wrong_commitment() {
+        let (min, max) = (1_000_u64, 1_100_u64);
+        let (comm, open) = Pedersen::new(1_055_u64);
+
+        let mut transcript_create = Transcript::new(b"Test");
+        let proof =
+            RangeProof::new_with_bounds(1_055, min, max, &open, &mut transcript_create).unwrap();
+
+        // the proof does not verify against a different commitment in the same range, whether to
+        // a different amount or to the same amount under a different opening
+        let (other_amount_comm, _) = Pedersen::new(1_056_u64);
+        let (other_opening_comm, _) = Pedersen::new(1_055_u64);
+
+        for wrong_comm in [&other_amount_comm, &other_opening_comm] {
+            let mut transcript_verify = Transcript::new(b"Test");
+            assert!(proof
+                .verify_with_bounds(wrong_comm, min, max, &mut transcript_verify)
+                .is_err());
+        }
+
+        let mut transcript_verify = Transcript::new(b"Test");
+        assert!(proof
+            .verify_with_bounds(&comm, min, max, &mut transcript_verify)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_rangeproof_with_bounds_out_of_range() {
+        let (min, max) = (1_000_u64, 1_100_u64);
+
+        for amount in [0, min - 1, max + 1, u64::MAX] {
+            let (_, open) = Pedersen::new(amount);
+
+            let mut transcript_create = Transcript::new(b"Test");
+            assert_eq!(
+                RangeProof::new_with_bounds(amount, min, max, &open, &mut transcript_create)
+                    .err()
+                    .unwrap(),
+                RangeProofGenerationError::ValueOutOfRange,
+            );
+        }
+    }
+
+    #[test]
+    fn test_rangeproof_with_bounds_invalid_range() {
+        let (comm, open) = Pedersen::new(1_055_u64);
+
+        let mut transcript_create = Transcript::new(b"Test");
+        assert_eq!(
+            RangeProof::new_with_bounds(1_055, 1_100, 1_000, &open, &mut transcript_create)
+                .err()
+                .unwrap(),
+            RangeProofGenerationError::ValueOutOfRange,
+        );
+
+        let mut transcript_create = Transcript::new(b"Test");
+        let proof = RangeProof::new_with_bounds(1_055, 1_000, 1_100, &open, &mut transcript_create)
+            .unwrap();
+
+        let mut transcript_verify = Transcript::new(b"Test");
+        assert_eq!(
+            proof.verify_with_bounds(&comm, 1_100, 1_000, &mut transcript_verify),
+            Err(RangeProofVerificationError::InvalidRange),
+        );
+    }
+
+    #[test]
+    fn test_bounded_bit_length() {
+        assert_eq!(bounded_bit_length(5, 5), 1);
+        assert_eq!(bounded_bit_length(5, 6), 1);
+        assert_eq!(bounded_bit_length(5, 7), 2);
+        assert_eq!(bounded_bit_length(1_000, 1_100), 8);
+        assert_eq!(bounded_bit_length(0, u32::MAX as u64), 32);
+        assert_eq!(bounded_bit_length(0, u32::MAX as u64 + 1), 64);
+        assert_eq!(bounded_bit_length(0, u64::MAX), 64);
+    }
+
+    #[test]
+    fn test_rangeproof_supported_bit_sizes() {
+        for &bit_size in supported_bit_sizes() {
+            // amounts are `u64`, so split larger bit sizes across several 64-bit commitments
+            let bit_length = bit_size.min(64);
+            let num_commitments = bit_size / bit_length;
+
+            let amount = 1_u64;
+            let (comms, opens): (Vec<_>, Vec<_>) =
+                (0..num_commitments).map(|_| Pedersen::new(amount)).unzip();
+
+            let mut transcript_create = Transcript::new(b"Test");
+            let mut transcript_verify = Transcript::new(b"Test");
+
+            let proof = RangeProof::new(
+                vec![amount; num_commitments],
+                vec![bit_length; num_commitments],
+                opens.iter().collect(),
+                &mut transcript_create,
+            )
+            .unwrap();
+
+            assert!(is_bit_size_supported(bit_size));
+            assert!(proof
+                .verify(
+                    comms.iter().collect(),
+                    vec![bit_length; num_commitments],
+                    &mut transcript_verify,
+                )
+                .is_ok());
+        }
+
+        assert!(!is_bit_size_supported(24));
+        assert!(!is_bit_size_supported(512));
+
+        let (comm, open) = Pedersen::new(55_u64);
+
+        let mut transcript_create = Transcript::new(b"Test");
+        assert_eq!(
+            RangeProof::new(vec![55], vec![24], vec![&open], &mut transcript_create)
+                .err()
+                .unwrap(),
+            RangeProofGenerationError::InvalidBitSize,
+        );
+
+        let mut transcript_create = Transcript::new(b"Test");
+        assert_eq!(
+            RangeProof::new(
+                vec![55; 8],
+                vec![64; 8],
+                vec![&open; 8],
+                &mut transcript_create
+            )
+            .err()
+            .unwrap(),
+            RangeProofGenerationError::BitSizeTooLarge,
+        );
+
+        let mut transcript_create = Transcript::new(b"Test");
+        let proof =
+            RangeProof::new(vec![55], vec![64], vec![&open], &mut transcript_create).unwrap();
+
+        let mut transcript_verify = Transcript::new(b"Test");
+        assert_eq!(
+            proof.verify(vec![&comm], vec![24], &mut transcript_verify),
+            Err(RangeProofVerificationError::InvalidBitSize),
+        );
+
+        let mut transcript_verify = Transcript::new(b"Test");
+        assert_eq!(
+            proof.verify(vec![&comm; 8], vec![64; 8], &mut transcript_verify),
+            Err(RangeProofVerificationError::BitSizeTooLarge),
+        );
+    }
+
+    #[test]
+    fn test_rangeproof_empty_batch() {
+        let (comm, open) = Pedersen::new(55_u64);
+
+        let mut transcript_create = Transcript::new(b"Test");
+        let proof =
+            RangeProof::new(vec![55], vec![64], vec![&open], &mut transcript_create).unwrap();
+
+        let mut transcript_verify = Transcript::new(b"Test");
+        assert_eq!(
+            proof.verify(vec![], vec![], &mut transcript_verify),
+            Err(RangeProofVerificationError::EmptyBatch),
+        );
+
+        let mut transcript_verify = Transcript::new(b"Test");
+        assert_eq!(
+            proof.verify(vec![&comm], vec![64], &mut transcript_verify),
+            Ok(()),
+        );
+    }
+
+    #[test]
+    fn test_rangeproof_inconsistent_input_lengths() {
+        let (_, open_1) = Pedersen::new(55_u64);
+        let (_, open_2) = Pedersen::new(77_u64);
+        let (_, open_3) = Pedersen::new(99_u64);
+
+        let mut transcript_create = Transcript::new(b"Test");
+
+        assert_eq!(
+            RangeProof::new(
+                vec![55, 77, 99],
+                vec![64, 64],
+                vec![&open_1, &open_2, &open_3],
+                &mut transcript_create,
+            )
+            .err()
+            .unwrap(),
+            RangeProofGenerationError::InconsistentInputLengths {
+                values: 3,
+                bit_sizes: 2,
+                blindings: 3,
+            },
+        );
+    }
+
+    #[test]
+    fn test_rangeproof_verify_inconsistent_input_lengths() {
+        let (comm_1, open_1) = Pedersen::new(55_u64);
+        let (comm_2, open_2) = Pedersen::new(77_u64);
+
+        let mut transcript_create = Transcript::new(b"Test");
+        let proof = RangeProof::new(
+            vec![55, 77],
+            vec![32, 32],
+            vec![&open_1, &open_2],
+            &mut transcript_create,
+        )
+        .unwrap();
+
+        let expected = Err(RangeProofVerificationError::InconsistentInputLengths {
+            commitments: 2,
+            bit_sizes: 1,
+        });
+
+        let mut transcript_verify = Transcript::new(b"Test");
+        assert_eq!(
+            proof.verify(vec![&comm_1, &comm_2], vec![64], &mut transcript_verify),
+            expected,
+        );
+
+        let mut transcript_verify = Transcript::new(b"Test");
+        assert_eq!(
+            proof.verify_with_generators(
+                &BulletproofGens::new(64),
+                vec![&comm_1, &comm_2],
+                vec![64],
+                &mut transcript_verify,
+            ),
+            expected,
+        );
+    }
+
+    #[test]
+    fn test_rangeproof_transcript_error_label() {
+        let (comm, open) = Pedersen::new(55_u64);
+
+        let mut transcript_create = Transcript::new(b"Test");
+        let proof =
+            RangeProof::new(vec![55], vec![32], vec![&open], &mut transcript_create).unwrap();
+
+        let mut corrupted_proof = proof.clone();
+        corrupted_proof.T_1 = CompressedRistretto::identity();
+
+        let mut transcript_verify = Transcript::new(b"Test");
+        assert_eq!(
+            corrupted_proof
+                .verify(vec![&comm], vec![32], &mut transcript_verify)
+                .unwrap_err(),
+            RangeProofVerificationError::Transcript {
+                label: "T_1".to_string(),
+                source: TranscriptError::ValidationError,
+            },
+        );
+
+        let mut corrupted_proof = proof;
+        corrupted_proof.ipp_proof.L_vec[1] = CompressedRistretto::identity();
+
+        let mut transcript_verify = Transcript::new(b"Test");
+        assert_eq!(
+            corrupted_proof
+                .verify(vec![&comm], vec![32], &mut transcript_verify)
+                .unwrap_err(),
+            RangeProofVerificationError::Transcript {
+                label: "L_1".to_string(),
+                source: TranscriptError::ValidationError,
+            },
+        );
+    }
+
+    #[test]
+    fn test_rangeproof_verify_with_generators() {
+        let bp_gens = BulletproofGens::new(64);
+
+        for amount in [0_u64, 1, 55, u32::MAX as u64, u64::MAX] {
+            let (comm, open) = Pedersen::new(amount);
+            let (wrong_comm, _) = Pedersen::new(amount.wrapping_add(1));
+
+            let mut transcript_create = Transcript::new(b"Test");
+            let proof =
+                RangeProof::new(vec![amount], vec![64], vec![&open], &mut transcript_create)
+                    .unwrap();
+
+            for comm in [&comm, &wrong_comm] {
+                let mut transcript_fresh = Transcript::new(b"Test");
+                let mut transcript_reused = Transcript::new(b"Test");
+
+                assert_eq!(
+                    proof.verify(vec![comm], vec![64], &mut transcript_fresh),
+                    proof.verify_with_generators(
+                        &bp_gens,
+                        vec![comm],
+                        vec![64],
+                        &mut transcript_reused
+                    ),
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_rangeproof_verify_with_generators_length() {
+        let (comm, open) = Pedersen::new(55_u64);
+
+        let mut transcript_create = Transcript::new(b"Test");
+        let proof =
+            RangeProof::new(vec![55], vec![64], vec![&open], &mut transcript_create).unwrap();
+
+        let mut transcript_verify = Transcript::new(b"Test");
+        assert_eq!(
+            proof.verify_with_generators(
+                &BulletproofGens::new(32),
+                vec![&comm],
+                vec![64],
+                &mut transcript_verify
+            ),
+            Err(RangeProofVerificationError::InvalidGeneratorsLength),
+        );
+
+        let mut transcript_verify = Transcript::new(b"Test");
+        assert_eq!(
+            proof.verify_with_generators(
+                &BulletproofGens::new(256),
+                vec![&comm],
+                vec![64],
+                &mut transcript_verify
+            ),
+            Ok(()),
+        );
+    }
+
     // TODO: write test for serialization/deserialization
 }